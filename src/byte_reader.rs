@@ -1,47 +1,181 @@
-use std::io::{BufWriter, Read, Result};
+use std::io;
+use std::io::{BufWriter, Read};
 use std::path::Path;
 
 use bitstream_io::{BitWriter, LittleEndian};
 use image::*;
 
+use crate::content_version::{
+    BitDepth, CompressionAlgorithm, ContentVersion, HEADER_LEN, SIGNATURE, SIGNATURE_LEN,
+};
+use crate::error::{Result, SteganoError};
+
 pub struct ByteReader {
     input: Option<RgbaImage>,
     x: u32,
     y: u32,
     c: usize,
+    bit: u8,
+    depth: BitDepth,
+    use_alpha: bool,
+    version: Option<ContentVersion>,
+    compression: CompressionAlgorithm,
+    remaining: Option<usize>,
 }
 
 impl ByteReader {
-    pub fn new(input_file: &str) -> Self {
+    pub fn new(input_file: &str) -> Result<Self> {
         ByteReader::of_file(Path::new(input_file))
     }
+
+    /// Like `new`, but with an explicit LSB `depth` (1..=4 bits per channel) and
+    /// whether the alpha channel should be used in addition to red/green/blue.
+    pub fn with_options(input_file: &str, depth: BitDepth, use_alpha: bool) -> Result<Self> {
+        ByteReader::of_file_with_options(Path::new(input_file), depth, use_alpha)
+    }
 }
 
 impl ByteReader {
-    pub fn of_file(input_file: &Path) -> Self {
-        ByteReader::of_image(image::open(input_file)
-            .expect("Input image is not readable.")
-            .to_rgba())
+    pub fn of_file(input_file: &Path) -> Result<Self> {
+        ByteReader::of_file_with_options(input_file, BitDepth::default(), false)
+    }
+
+    /// Like `of_file`, but with an explicit LSB `depth` (1..=4 bits per channel) and
+    /// whether the alpha channel should be used in addition to red/green/blue.
+    pub fn of_file_with_options(
+        input_file: &Path,
+        depth: BitDepth,
+        use_alpha: bool,
+    ) -> Result<Self> {
+        let image = image::open(input_file)
+            .map_err(|_| SteganoError::ImageUnreadable(input_file.display().to_string()))?;
+        ByteReader::of_image_with_options(image.to_rgba(), depth, use_alpha)
     }
 }
 
 impl ByteReader {
-    pub fn of_image(image: RgbaImage) -> Self {
-        ByteReader {
+    pub fn of_image(image: RgbaImage) -> Result<Self> {
+        ByteReader::of_image_with_options(image, BitDepth::default(), false)
+    }
+
+    /// Like `of_image`, but with an explicit LSB `depth` (1..=4 bits per channel) and
+    /// whether the alpha channel should be used in addition to red/green/blue.
+    pub fn of_image_with_options(image: RgbaImage, depth: BitDepth, use_alpha: bool) -> Result<Self> {
+        Ok(ByteReader {
             input: Some(image),
             x: 0,
             y: 0,
             c: 0,
+            bit: 0,
+            depth,
+            use_alpha,
+            version: None,
+            compression: CompressionAlgorithm::None,
+            remaining: None,
+        })
+    }
+}
+
+impl ByteReader {
+    /// Reads and validates the content header without consuming the hidden payload
+    /// itself. Returns `Some(version)` once a known signature was found, after which
+    /// `read` stops once the declared payload length has been delivered. Returns
+    /// `None` when no header is present, in which case `read` keeps behaving like the
+    /// raw, unterminated `V1` bitstream for backward compatibility.
+    pub fn detect(&mut self) -> Result<Option<ContentVersion>> {
+        let origin = (self.x, self.y, self.c, self.bit);
+        let caller_depth = self.depth;
+        let caller_use_alpha = self.use_alpha;
+
+        // The header is always embedded at a canonical depth-1, RGB-only encoding,
+        // regardless of what this reader was constructed with, so that any reader
+        // can bootstrap-discover the real payload depth/alpha from it.
+        self.depth = BitDepth::default();
+        self.use_alpha = false;
+        let mut header = [0u8; HEADER_LEN];
+        let read_result = self.read(&mut header);
+
+        self.depth = caller_depth;
+        self.use_alpha = caller_use_alpha;
+
+        let n = read_result?;
+
+        if n != header.len() || header[..SIGNATURE_LEN] != SIGNATURE[..] {
+            self.x = origin.0;
+            self.y = origin.1;
+            self.c = origin.2;
+            self.bit = origin.3;
+            return Ok(None);
+        }
+
+        let version = ContentVersion::from(header[SIGNATURE_LEN]);
+        let compression = CompressionAlgorithm::from(header[SIGNATURE_LEN + 1]);
+        let (depth, use_alpha) = BitDepth::from_encoding_byte(header[SIGNATURE_LEN + 2]);
+        let length = u32::from_le_bytes([
+            header[SIGNATURE_LEN + 3],
+            header[SIGNATURE_LEN + 4],
+            header[SIGNATURE_LEN + 5],
+            header[SIGNATURE_LEN + 6],
+        ]) as usize;
+
+        self.version = Some(version);
+        self.compression = compression;
+        self.depth = depth;
+        self.use_alpha = use_alpha;
+        self.remaining = Some(length);
+
+        Ok(Some(version))
+    }
+
+    /// The content version found by the last call to `detect`, if any.
+    pub fn content_version(&self) -> Option<ContentVersion> {
+        self.version
+    }
+
+    /// The number of channels this reader pulls bits from: 3 (RGB), or 4 when
+    /// `use_alpha` is enabled.
+    fn channel_count(&self) -> usize {
+        if self.use_alpha {
+            4
+        } else {
+            3
         }
     }
+
+    /// The number of bytes this image can carry at the reader's current bit depth
+    /// and channel count, so callers can pick the smallest depth that fits their
+    /// payload.
+    pub fn capacity(&self) -> usize {
+        let (width, height) = self
+            .input
+            .as_ref()
+            .map(RgbaImage::dimensions)
+            .unwrap_or((0, 0));
+        let bits_per_pixel = self.channel_count() * self.depth.bits() as usize;
+        (width as usize * height as usize * bits_per_pixel) / 8
+    }
+
+    /// The compression algorithm declared by the last call to `detect`, defaulting to
+    /// `CompressionAlgorithm::None` for a legacy, headerless payload.
+    pub fn compression(&self) -> CompressionAlgorithm {
+        self.compression
+    }
+
+    /// Consumes this reader and wraps it in a [`crate::decompress::PayloadDecoder`]
+    /// matching the compression algorithm found by `detect`, so that reading from the
+    /// result yields the original, already-decompressed payload bytes.
+    pub fn into_decoder(self) -> Result<crate::decompress::PayloadDecoder> {
+        let algorithm = self.compression;
+        crate::decompress::PayloadDecoder::new(self, algorithm)
+    }
 }
 
 impl Read for ByteReader {
-    fn read(&mut self, b: &mut [u8]) -> Result<usize> {
+    fn read(&mut self, b: &mut [u8]) -> io::Result<usize> {
         #[inline]
         #[cfg(debug_assertions)]
-        fn update_progress(total_progress: u32, progress: &mut u8, x: u32, y: u32) {
-            let p = ((x * y * 100) / total_progress) as u8;
+        fn update_progress(total_progress: u64, progress: &mut u8, x: u32, y: u32) {
+            let p = ((x as u64 * y as u64 * 100) / total_progress) as u8;
             if p > *progress {
                 *progress = p;
                 print!("\rProgress: {}%", p);
@@ -52,46 +186,68 @@ impl Read for ByteReader {
         }
         #[inline]
         #[cfg(not(debug_assertions))]
-        fn update_progress(total_progress: u32, progress: &mut u8, x: u32, y: u32) {
-            let p = ((x * y * 100) / total_progress) as u8;
+        fn update_progress(total_progress: u64, progress: &mut u8, x: u32, y: u32) {
+            let p = ((x as u64 * y as u64 * 100) / total_progress) as u8;
             if p > *progress {
                 *progress = p;
             }
         }
 
+        if self.remaining == Some(0) {
+            return Ok(0);
+        }
+
         let source_image = self.input.as_ref().unwrap();
         let (width, height) = source_image.dimensions();
-        let bytes_to_read = b.len();
-        let total_progress = width * height;
-        let mut buf_writer = BufWriter::new(b);
+        let channels = self.channel_count();
+        let n = self.depth.bits();
+        let mask = (1u8 << n) - 1;
+        let bytes_to_read = match self.remaining {
+            Some(remaining) => b.len().min(remaining),
+            None => b.len(),
+        };
+        let total_progress = width as u64 * height as u64 * channels as u64 * n as u64;
+        let mut buf_writer = BufWriter::new(&mut b[..bytes_to_read]);
 
         let mut bit_buffer = BitWriter::endian(
             buf_writer,
             LittleEndian,
         );
 
-        let mut progress: u8 = ((self.x * self.y * 100) / total_progress) as u8;
+        let mut progress: u8 = ((self.x as u64 * self.y as u64 * 100) / total_progress) as u8;
         let mut bits_read = 0;
         let mut bytes_read = 0;
         for x in self.x..width {
             for y in self.y..height {
                 let image::Rgba(rgba) = source_image.get_pixel(x, y);
-                for c in self.c..3 {
-                    if bytes_read >= bytes_to_read {
-                        self.x = x;
-                        self.y = y;
-                        self.c = c;
-                        return Ok(bytes_read);
+                for c in self.c..channels {
+                    for bit in self.bit..n {
+                        if bytes_read >= bytes_to_read {
+                            self.x = x;
+                            self.y = y;
+                            self.c = c;
+                            self.bit = bit;
+                            if let Some(remaining) = self.remaining.as_mut() {
+                                *remaining -= bytes_read;
+                            }
+                            return Ok(bytes_read);
+                        }
+                        let value = (rgba[c] & mask) >> bit;
+                        bit_buffer.write_bit((value & 0x01) > 0).map_err(|_| {
+                            io::Error::new(
+                                io::ErrorKind::Other,
+                                SteganoError::BitWrite { x, y, c },
+                            )
+                        })?;
+                        bits_read += 1;
+
+                        if bits_read % 8 == 0 {
+                            bytes_read = (bits_read / 8) as usize;
+                            update_progress(total_progress, &mut progress, x, y);
+                        }
                     }
-                    let bit = rgba[c] & 0x01;
-                    bit_buffer
-                        .write_bit(bit > 0)
-                        .unwrap_or_else(|_| panic!("Color {} on Pixel({}, {})", c, x, y));
-                    bits_read += 1;
-
-                    if bits_read % 8 == 0 {
-                        bytes_read = (bits_read / 8) as usize;
-                        update_progress(total_progress, &mut progress, x, y);
+                    if self.bit > 0 {
+                        self.bit = 0;
                     }
                 }
                 if self.c > 0 {
@@ -106,6 +262,9 @@ impl Read for ByteReader {
         if !bit_buffer.byte_aligned() {
             bit_buffer.byte_align();
         }
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= bytes_read;
+        }
 
         return Ok(bytes_read);
     }
@@ -128,7 +287,7 @@ mod tests {
 
     #[test]
     fn test_read_trait_behaviour_for_read_once() {
-        let mut dec = ByteReader::new(HELLO_WORLD_PNG);
+        let mut dec = ByteReader::new(HELLO_WORLD_PNG).unwrap();
 
         let mut buf = [0 as u8; 13];
         let r = dec.read(&mut buf).unwrap();
@@ -144,7 +303,7 @@ mod tests {
 
     #[test]
     fn test_read_trait_behaviour_for_read_multiple_times() {
-        let mut dec = ByteReader::new(HELLO_WORLD_PNG);
+        let mut dec = ByteReader::new(HELLO_WORLD_PNG).unwrap();
 
         let mut buf = [0 as u8; 3];
         let r = dec.read(&mut buf).unwrap();
@@ -164,7 +323,7 @@ mod tests {
 
     #[test]
     fn test_read_trait_behaviour_for_read_all() {
-        let mut dec = ByteReader::new(HELLO_WORLD_PNG);
+        let mut dec = ByteReader::new(HELLO_WORLD_PNG).unwrap();
         let expected_bytes = ((515 * 443 * 3) / 8) as usize;
 
         let mut buf = Vec::new();
@@ -177,7 +336,7 @@ mod tests {
 
     #[test]
     fn should_not_contain_noise_bytes() {
-        let mut dec = ByteReader::new(CARGO_ZIP_PNG);
+        let mut dec = ByteReader::new(CARGO_ZIP_PNG).unwrap();
         let expected_bytes = ((515 * 443 * 3) / 8) as usize;
         let zip_file_size = 337;
 
@@ -203,6 +362,71 @@ mod tests {
 //        }
     }
 
+    #[test]
+    fn test_detect_falls_back_to_legacy_v1_when_no_header_present() {
+        let mut dec = ByteReader::new(HELLO_WORLD_PNG).unwrap();
+        let expected_bytes = ((515 * 443 * 3) / 8) as usize;
+
+        let detected = dec.detect().unwrap();
+        assert_eq!(detected, None, "legacy image carries no content header");
+        assert_eq!(dec.content_version(), None);
+
+        let mut buf = Vec::new();
+        let r = dec.read_to_end(&mut buf).unwrap();
+        assert_eq!(
+            r, expected_bytes,
+            "detect must not consume any bytes from a headerless image"
+        );
+        assert_eq!(buf[0], 0x1, "1st byte does not match");
+        assert_eq!(buf[1], H, "2nd byte is not a 'H'");
+    }
+
+    #[test]
+    fn test_read_honors_configured_bit_depth_and_alpha_channel() {
+        // A single pixel at depth 2 over R, G, B, A carries 8 bits, LSB-first per
+        // channel: only the alpha channel's two bits are set here, landing in the
+        // top two bits of the decoded byte.
+        let mut image = RgbaImage::from_pixel(1, 1, Rgba([0, 0, 0, 0]));
+        image.put_pixel(0, 0, Rgba([0b00, 0b00, 0b00, 0b11]));
+
+        let mut dec = ByteReader::of_image_with_options(image, BitDepth::new(2), true).unwrap();
+        assert_eq!(dec.capacity(), (1 * 1 * 4 * 2) / 8);
+
+        let mut buf = [0u8; 1];
+        let r = dec.read(&mut buf).unwrap();
+        assert_eq!(r, 1);
+        assert_eq!(buf[0], 0b1100_0000);
+    }
+
+    #[test]
+    fn test_detect_finds_header_regardless_of_constructed_depth_and_alpha() {
+        use std::io::Write;
+
+        use crate::byte_writer::ByteWriter;
+
+        let image = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 255]));
+        let mut writer = ByteWriter::of_image(image).unwrap();
+        let payload = b"Hi!";
+        writer
+            .write_header(payload.len() as u32, CompressionAlgorithm::None)
+            .unwrap();
+        writer.write_all(payload).unwrap();
+
+        // Constructed with a depth/alpha that does not match the header's canonical
+        // depth-1, RGB-only encoding: `detect` must still find the header by parsing
+        // it at the canonical encoding, then switch to the declared payload encoding.
+        let mut dec =
+            ByteReader::of_image_with_options(writer.into_image(), BitDepth::new(3), true)
+                .unwrap();
+
+        let version = dec.detect().unwrap();
+        assert_eq!(version, Some(ContentVersion::V2));
+
+        let mut buf = Vec::new();
+        dec.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, payload);
+    }
+
     #[test]
     fn test_bit_writer() {
         let b = vec![0b0100_1000, 0b0110_0001, 0b0110_1100];
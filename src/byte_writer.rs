@@ -0,0 +1,273 @@
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+use bitstream_io::{BitReader, LittleEndian};
+use image::*;
+
+use crate::content_version::{BitDepth, CompressionAlgorithm, ContentVersion, SIGNATURE};
+use crate::error::{Result, SteganoError};
+
+pub struct ByteWriter {
+    output: RgbaImage,
+    x: u32,
+    y: u32,
+    c: usize,
+    bit: u8,
+    depth: BitDepth,
+    use_alpha: bool,
+}
+
+impl ByteWriter {
+    pub fn new(input_file: &str) -> Result<Self> {
+        ByteWriter::of_file(Path::new(input_file))
+    }
+
+    /// Like `new`, but with an explicit LSB `depth` (1..=4 bits per channel) and
+    /// whether the alpha channel should be used in addition to red/green/blue.
+    pub fn with_options(input_file: &str, depth: BitDepth, use_alpha: bool) -> Result<Self> {
+        ByteWriter::of_file_with_options(Path::new(input_file), depth, use_alpha)
+    }
+}
+
+impl ByteWriter {
+    pub fn of_file(input_file: &Path) -> Result<Self> {
+        ByteWriter::of_file_with_options(input_file, BitDepth::default(), false)
+    }
+
+    /// Like `of_file`, but with an explicit LSB `depth` (1..=4 bits per channel) and
+    /// whether the alpha channel should be used in addition to red/green/blue.
+    pub fn of_file_with_options(
+        input_file: &Path,
+        depth: BitDepth,
+        use_alpha: bool,
+    ) -> Result<Self> {
+        let image = image::open(input_file)
+            .map_err(|_| SteganoError::ImageUnreadable(input_file.display().to_string()))?;
+        ByteWriter::of_image_with_options(image.to_rgba(), depth, use_alpha)
+    }
+}
+
+impl ByteWriter {
+    pub fn of_image(image: RgbaImage) -> Result<Self> {
+        ByteWriter::of_image_with_options(image, BitDepth::default(), false)
+    }
+
+    /// Like `of_image`, but with an explicit LSB `depth` (1..=4 bits per channel) and
+    /// whether the alpha channel should be used in addition to red/green/blue.
+    pub fn of_image_with_options(image: RgbaImage, depth: BitDepth, use_alpha: bool) -> Result<Self> {
+        Ok(ByteWriter {
+            output: image,
+            x: 0,
+            y: 0,
+            c: 0,
+            bit: 0,
+            depth,
+            use_alpha,
+        })
+    }
+}
+
+impl ByteWriter {
+    /// The number of channels this writer puts bits into: 3 (RGB), or 4 when
+    /// `use_alpha` is enabled.
+    fn channel_count(&self) -> usize {
+        if self.use_alpha {
+            4
+        } else {
+            3
+        }
+    }
+
+    /// The number of bytes this image can carry at the writer's current bit depth
+    /// and channel count.
+    pub fn capacity(&self) -> usize {
+        let (width, height) = self.output.dimensions();
+        let bits_per_pixel = self.channel_count() * self.depth.bits() as usize;
+        (width as usize * height as usize * bits_per_pixel) / 8
+    }
+
+    /// Consumes this writer, returning the image with the payload embedded in it.
+    pub fn into_image(self) -> RgbaImage {
+        self.output
+    }
+
+    /// Consumes this writer, saving the image with the payload embedded in it.
+    pub fn save(self, output_file: &Path) -> Result<()> {
+        self.output
+            .save(output_file)
+            .map_err(|e| SteganoError::Io(e.to_string()))
+    }
+
+    /// Writes the content header (`SIGNATURE` + version + `compression` + this
+    /// writer's configured bit-depth/alpha encoding + payload length) that
+    /// `ByteReader::detect` looks for, so the `payload_len` bytes written right
+    /// after this call can be found again. Always emits `ContentVersion::V2`. The
+    /// header itself is always written at the canonical depth-1, RGB-only encoding
+    /// regardless of the depth/alpha this writer was constructed with; the payload
+    /// written after it uses that depth/alpha.
+    pub fn write_header(
+        &mut self,
+        payload_len: u32,
+        compression: CompressionAlgorithm,
+    ) -> io::Result<()> {
+        let depth = self.depth;
+        let use_alpha = self.use_alpha;
+
+        let mut header = Vec::with_capacity(SIGNATURE.len() + 1 + 1 + 1 + 4);
+        header.extend_from_slice(&SIGNATURE);
+        header.push(ContentVersion::V2.into());
+        header.push(compression.into());
+        header.push(depth.to_encoding_byte(use_alpha));
+        header.extend_from_slice(&payload_len.to_le_bytes());
+
+        self.depth = BitDepth::default();
+        self.use_alpha = false;
+        let result = self.write_all(&header);
+        self.depth = depth;
+        self.use_alpha = use_alpha;
+
+        result
+    }
+}
+
+impl Write for ByteWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let (width, height) = self.output.dimensions();
+        let channels = self.channel_count();
+        let n = self.depth.bits();
+        let bytes_to_write = buf.len();
+        let mut bit_reader = BitReader::endian(buf, LittleEndian);
+
+        let mut bits_written = 0;
+        let mut bytes_written = 0;
+        for x in self.x..width {
+            for y in self.y..height {
+                let pixel = self.output.get_pixel_mut(x, y);
+                for c in self.c..channels {
+                    for bit in self.bit..n {
+                        if bytes_written >= bytes_to_write {
+                            self.x = x;
+                            self.y = y;
+                            self.c = c;
+                            self.bit = bit;
+                            return Ok(bytes_written);
+                        }
+                        let value: bool = bit_reader.read_bit().map_err(|_| {
+                            io::Error::new(io::ErrorKind::Other, SteganoError::BitWrite { x, y, c })
+                        })?;
+                        pixel[c] = (pixel[c] & !(1u8 << bit)) | ((value as u8) << bit);
+                        bits_written += 1;
+
+                        if bits_written % 8 == 0 {
+                            bytes_written = bits_written / 8;
+                        }
+                    }
+                    if self.bit > 0 {
+                        self.bit = 0;
+                    }
+                }
+                if self.c > 0 {
+                    self.c = 0;
+                }
+            }
+            if self.y > 0 {
+                self.y = 0;
+            }
+        }
+        self.x = width;
+
+        if bytes_written < bytes_to_write {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                SteganoError::TooSmallToCarryData,
+            ));
+        }
+
+        Ok(bytes_written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::byte_reader::ByteReader;
+
+    #[test]
+    fn test_write_then_read_round_trips() {
+        let image = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 255]));
+        let mut writer = ByteWriter::of_image(image).unwrap();
+
+        let payload = b"Hi!";
+        let written = writer.write(payload).unwrap();
+        assert_eq!(written, payload.len());
+
+        let mut reader = ByteReader::of_image(writer.into_image()).unwrap();
+        let mut buf = [0u8; 3];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, payload);
+    }
+
+    #[test]
+    fn test_write_header_then_detect_round_trips() {
+        let image = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 255]));
+        let mut writer = ByteWriter::of_image(image).unwrap();
+
+        let payload = b"Hi!";
+        writer
+            .write_header(payload.len() as u32, CompressionAlgorithm::None)
+            .unwrap();
+        writer.write_all(payload).unwrap();
+
+        let mut reader = ByteReader::of_image(writer.into_image()).unwrap();
+        let version = reader.detect().unwrap();
+        assert_eq!(version, Some(ContentVersion::V2));
+        assert_eq!(reader.compression(), CompressionAlgorithm::None);
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, payload);
+    }
+
+    #[test]
+    fn test_write_rejects_payload_larger_than_capacity() {
+        let image = RgbaImage::from_pixel(2, 2, Rgba([0, 0, 0, 255]));
+        let mut writer = ByteWriter::of_image(image).unwrap();
+        assert_eq!(writer.capacity(), (2 * 2 * 3) / 8);
+
+        let payload = vec![0u8; writer.capacity() + 1];
+        let err = writer.write(&payload).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_write_honors_configured_bit_depth_across_pixels_and_calls() {
+        // Depth 2 over R, G, B (no alpha): 6 bits/pixel, so a byte boundary falls
+        // mid-pixel. Three pixels give 18 bits of capacity, enough for two bytes,
+        // written across two separate `write` calls to prove the (x, y, c, bit)
+        // cursor survives both a partial write and a pixel-boundary crossing.
+        let image = RgbaImage::from_pixel(3, 1, Rgba([0, 0, 0, 255]));
+        let mut writer =
+            ByteWriter::of_image_with_options(image, BitDepth::new(2), false).unwrap();
+        assert_eq!(writer.capacity(), (3 * 1 * 3 * 2) / 8);
+
+        let payload = [0b1011_0001, 0b0100_1110];
+        let written_first = writer.write(&payload[..1]).unwrap();
+        assert_eq!(written_first, 1);
+        let written_second = writer.write(&payload[1..]).unwrap();
+        assert_eq!(written_second, 1);
+
+        let mut reader =
+            ByteReader::of_image_with_options(writer.into_image(), BitDepth::new(2), false)
+                .unwrap();
+        let mut buf = [0u8; 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, payload);
+    }
+}
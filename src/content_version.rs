@@ -0,0 +1,176 @@
+/// Magic signature written ahead of every versioned payload.
+pub const SIGNATURE: [u8; 8] = [0xEE, b'm', b'b', b'n', 0x0D, 0x0A, 0x1A, 0x00];
+
+/// Number of bytes the signature occupies.
+pub const SIGNATURE_LEN: usize = SIGNATURE.len();
+
+/// Total size of `SIGNATURE` + version byte + compression byte + bit-depth/alpha
+/// encoding byte + little-endian `u32` payload length.
+pub const HEADER_LEN: usize = SIGNATURE_LEN + 1 + 1 + 1 + 4;
+
+/// The content-version byte that immediately follows the signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentVersion {
+    /// Raw bitstream payload with no header, for backward compatibility.
+    V1,
+    /// Length-terminated payload.
+    V2,
+    /// Length-terminated payload, reserved for a future bit-depth/channel layout.
+    V4,
+    /// A version byte this crate does not know how to decode.
+    Unsupported(u8),
+}
+
+impl From<u8> for ContentVersion {
+    fn from(byte: u8) -> Self {
+        match byte {
+            1 => ContentVersion::V1,
+            2 => ContentVersion::V2,
+            4 => ContentVersion::V4,
+            other => ContentVersion::Unsupported(other),
+        }
+    }
+}
+
+impl From<ContentVersion> for u8 {
+    fn from(version: ContentVersion) -> Self {
+        match version {
+            ContentVersion::V1 => 1,
+            ContentVersion::V2 => 2,
+            ContentVersion::V4 => 4,
+            ContentVersion::Unsupported(byte) => byte,
+        }
+    }
+}
+
+/// The compression-algorithm byte that follows the content version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    None,
+    Zlib,
+    Zstd,
+    Lzma,
+}
+
+impl From<u8> for CompressionAlgorithm {
+    fn from(byte: u8) -> Self {
+        match byte {
+            1 => CompressionAlgorithm::Zlib,
+            2 => CompressionAlgorithm::Zstd,
+            3 => CompressionAlgorithm::Lzma,
+            _ => CompressionAlgorithm::None,
+        }
+    }
+}
+
+impl From<CompressionAlgorithm> for u8 {
+    fn from(algorithm: CompressionAlgorithm) -> Self {
+        match algorithm {
+            CompressionAlgorithm::None => 0,
+            CompressionAlgorithm::Zlib => 1,
+            CompressionAlgorithm::Zstd => 2,
+            CompressionAlgorithm::Lzma => 3,
+        }
+    }
+}
+
+/// How many of the low bits of each color channel carry hidden data. A shallower
+/// depth introduces less visual distortion; a deeper one raises capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitDepth(u8);
+
+impl BitDepth {
+    /// Creates a new `BitDepth`. Panics if `bits` is outside `1..=4`.
+    pub fn new(bits: u8) -> Self {
+        assert!(
+            (1..=4).contains(&bits),
+            "bit depth must be between 1 and 4, got {}",
+            bits
+        );
+        BitDepth(bits)
+    }
+
+    /// The number of low bits of a channel used to carry data.
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Packs this depth and the `use_alpha` flag into the single encoding byte stored
+    /// in the content header.
+    pub fn to_encoding_byte(self, use_alpha: bool) -> u8 {
+        self.0 | ((use_alpha as u8) << 3)
+    }
+
+    /// Unpacks a depth and `use_alpha` flag from an encoding byte written by
+    /// `to_encoding_byte`.
+    pub fn from_encoding_byte(byte: u8) -> (Self, bool) {
+        let bits = (byte & 0b0000_0111).clamp(1, 4);
+        let use_alpha = (byte & 0b0000_1000) != 0;
+        (BitDepth(bits), use_alpha)
+    }
+}
+
+impl Default for BitDepth {
+    fn default() -> Self {
+        BitDepth(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_version_round_trips_through_u8() {
+        for version in [ContentVersion::V1, ContentVersion::V2, ContentVersion::V4] {
+            assert_eq!(ContentVersion::from(u8::from(version)), version);
+        }
+        assert_eq!(ContentVersion::from(99), ContentVersion::Unsupported(99));
+        assert_eq!(u8::from(ContentVersion::Unsupported(99)), 99);
+    }
+
+    #[test]
+    fn test_compression_algorithm_round_trips_through_u8() {
+        for algorithm in [
+            CompressionAlgorithm::None,
+            CompressionAlgorithm::Zlib,
+            CompressionAlgorithm::Zstd,
+            CompressionAlgorithm::Lzma,
+        ] {
+            assert_eq!(CompressionAlgorithm::from(u8::from(algorithm)), algorithm);
+        }
+    }
+
+    #[test]
+    fn test_compression_algorithm_from_unknown_byte_defaults_to_none() {
+        assert_eq!(CompressionAlgorithm::from(42), CompressionAlgorithm::None);
+    }
+
+    #[test]
+    fn test_bit_depth_encoding_byte_round_trips_with_and_without_alpha() {
+        for bits in 1..=4 {
+            let depth = BitDepth::new(bits);
+            let (decoded, use_alpha) = BitDepth::from_encoding_byte(depth.to_encoding_byte(false));
+            assert_eq!(decoded.bits(), bits);
+            assert!(!use_alpha);
+
+            let (decoded, use_alpha) = BitDepth::from_encoding_byte(depth.to_encoding_byte(true));
+            assert_eq!(decoded.bits(), bits);
+            assert!(use_alpha);
+        }
+    }
+
+    #[test]
+    fn test_bit_depth_from_encoding_byte_clamps_out_of_range_depth() {
+        // The low 3 bits can encode 0..=7, but only 1..=4 are valid depths; an
+        // out-of-range value should clamp into that range rather than silently
+        // producing a depth nothing else in the crate can handle.
+        let (decoded, use_alpha) = BitDepth::from_encoding_byte(0b0000_0111);
+        assert_eq!(decoded.bits(), 4);
+        assert!(!use_alpha);
+
+        let (decoded, use_alpha) = BitDepth::from_encoding_byte(0b0000_0000);
+        assert_eq!(decoded.bits(), 1);
+        assert!(!use_alpha);
+    }
+}
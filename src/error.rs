@@ -0,0 +1,52 @@
+use std::fmt;
+use std::io;
+
+/// Errors that can occur while reading or writing steganographic data.
+#[derive(Debug)]
+pub enum SteganoError {
+    /// The input image could not be opened or decoded.
+    ImageUnreadable(String),
+    /// The image's color type cannot be used to carry or extract data.
+    UnsupportedColorType,
+    /// A bit could not be written to the given pixel channel.
+    BitWrite { x: u32, y: u32, c: usize },
+    /// The image does not have enough pixels to carry the requested data.
+    TooSmallToCarryData,
+    /// An underlying I/O failure occurred while reading or writing bits.
+    Io(String),
+    /// The compressed payload could not be inflated by the declared algorithm.
+    Decompression(String),
+}
+
+impl fmt::Display for SteganoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SteganoError::ImageUnreadable(path) => {
+                write!(f, "input image is not readable: {}", path)
+            }
+            SteganoError::UnsupportedColorType => write!(f, "unsupported color type"),
+            SteganoError::BitWrite { x, y, c } => write!(
+                f,
+                "failed to write bit for channel {} on pixel ({}, {})",
+                c, x, y
+            ),
+            SteganoError::TooSmallToCarryData => {
+                write!(f, "image is too small to carry the requested data")
+            }
+            SteganoError::Io(message) => write!(f, "I/O error: {}", message),
+            SteganoError::Decompression(message) => {
+                write!(f, "failed to decompress payload: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SteganoError {}
+
+impl From<io::Error> for SteganoError {
+    fn from(err: io::Error) -> Self {
+        SteganoError::Io(err.to_string())
+    }
+}
+
+pub type Result<T> = core::result::Result<T, SteganoError>;
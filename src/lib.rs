@@ -0,0 +1,8 @@
+pub mod byte_reader;
+pub mod byte_writer;
+pub mod content_version;
+pub mod decompress;
+pub mod error;
+
+pub use content_version::{BitDepth, CompressionAlgorithm, ContentVersion};
+pub use error::{Result, SteganoError};